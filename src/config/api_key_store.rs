@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use rand::RngCore;
+use crate::config::error::{EdgeError, Result};
+use crate::models::api_key::{ApiKeyRecord, CreateApiKey, CreatedApiKey, KeyScope};
+
+// 通过API key访问时,中间件校验出的身份信息
+#[derive(Debug, Clone)]
+pub struct ApiKeyPrincipal {
+    pub id: i64,
+    pub name: String,
+    pub scope: KeyScope,
+    pub device_ids: Option<Vec<i32>>,
+}
+
+// 哈希后的key -> 身份的内存索引,撤销立即从这里摘除,鉴权不必每次查库
+static KEY_INDEX: OnceLock<RwLock<HashMap<String, ApiKeyPrincipal>>> = OnceLock::new();
+
+fn key_index() -> &'static RwLock<HashMap<String, ApiKeyPrincipal>> {
+    KEY_INDEX.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+fn hash_key(raw_key: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(raw_key.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+fn generate_raw_key() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("yke_{}", hex::encode(bytes))
+}
+
+// 服务启动时把未撤销的key加载进内存索引
+pub async fn init(pool: &SqlitePool) -> Result<()> {
+    let records = sqlx::query_as::<_, ApiKeyRecord>("SELECT * FROM tb_api_key WHERE revoked = 0")
+        .fetch_all(pool)
+        .await?;
+    let mut index = key_index().write().unwrap();
+    for record in records {
+        index.insert(record.hashed_key.clone(), ApiKeyPrincipal {
+            id: record.id,
+            name: record.name,
+            scope: record.scope,
+            device_ids: record.device_ids.map(|j| j.0),
+        });
+    }
+    Ok(())
+}
+
+pub async fn create(pool: &SqlitePool, create: CreateApiKey) -> Result<CreatedApiKey> {
+    let raw_key = generate_raw_key();
+    let hashed_key = hash_key(&raw_key);
+
+    let record = sqlx::query_as::<_, ApiKeyRecord>(
+        "INSERT INTO tb_api_key (name, hashed_key, scope, device_ids, revoked) VALUES (?, ?, ?, ?, 0) RETURNING *",
+    )
+        .bind(&create.name)
+        .bind(&hashed_key)
+        .bind(create.scope)
+        .bind(create.device_ids.map(sqlx::types::Json))
+        .fetch_one(pool)
+        .await?;
+
+    key_index().write().unwrap().insert(hashed_key, ApiKeyPrincipal {
+        id: record.id,
+        name: record.name.clone(),
+        scope: record.scope,
+        device_ids: record.device_ids.map(|j| j.0),
+    });
+
+    Ok(CreatedApiKey { id: record.id, name: record.name, key: raw_key })
+}
+
+pub async fn list(pool: &SqlitePool) -> Result<Vec<ApiKeyRecord>> {
+    Ok(sqlx::query_as::<_, ApiKeyRecord>("SELECT * FROM tb_api_key ORDER BY id")
+        .fetch_all(pool)
+        .await?)
+}
+
+// 撤销立即生效:先从内存索引摘除,再落库,保证撤销瞬间请求就会被拒绝
+pub async fn revoke(pool: &SqlitePool, id: i64) -> Result<()> {
+    let record = sqlx::query_as::<_, ApiKeyRecord>("SELECT * FROM tb_api_key WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| EdgeError::Message("api key不存在".into()))?;
+
+    key_index().write().unwrap().remove(&record.hashed_key);
+
+    sqlx::query("UPDATE tb_api_key SET revoked = 1 WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+// 鉴权中间件调用的校验入口,纯内存查找,不涉及异步IO
+pub fn verify(raw_key: &str) -> Option<ApiKeyPrincipal> {
+    key_index().read().unwrap().get(&hash_key(raw_key)).cloned()
+}
+
+impl ApiKeyPrincipal {
+    pub fn can_write(&self) -> bool {
+        self.scope == KeyScope::ReadWrite
+    }
+
+    pub fn can_access_device(&self, device_id: i32) -> bool {
+        match &self.device_ids {
+            Some(ids) => ids.contains(&device_id),
+            None => true,
+        }
+    }
+
+    //是否限定了设备范围,/ws 订阅等没有具体device_id可比对的场景需要知道这一点来决定是放行还是拒绝
+    pub fn is_device_scoped(&self) -> bool {
+        self.device_ids.is_some()
+    }
+}
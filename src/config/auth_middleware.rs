@@ -0,0 +1,71 @@
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use crate::config::api_key_store::{self, ApiKeyPrincipal};
+use crate::config::error::EdgeError;
+use crate::handler::auth_handler::Claims;
+
+const API_KEY_HEADER: &str = "x-api-key";
+
+// 统一的鉴权身份:交互式JWT用户,或headless集成用的API key。
+// 接口路由层用它替换掉单纯的Claims提取器,按Principal::ApiKey的scope再做读写校验
+#[derive(Debug, Clone)]
+pub enum Principal {
+    User(Claims),
+    ApiKey(ApiKeyPrincipal),
+}
+
+impl Principal {
+    //返回false时调用方应以EdgeError拒绝写操作
+    pub fn can_write(&self) -> bool {
+        match self {
+            Principal::User(_) => true,
+            Principal::ApiKey(key) => key.can_write(),
+        }
+    }
+
+    pub fn can_access_device(&self, device_id: i32) -> bool {
+        match self {
+            Principal::User(_) => true,
+            Principal::ApiKey(key) => key.can_access_device(device_id),
+        }
+    }
+
+    //是否限定了设备范围,true时对端没有device_id信息的更新应保守地拒绝转发而不是放行
+    pub fn is_device_scoped(&self) -> bool {
+        match self {
+            Principal::User(_) => false,
+            Principal::ApiKey(key) => key.is_device_scoped(),
+        }
+    }
+
+    //api key管理等敏感操作只允许交互式登录用户,拒绝api key自我提权
+    pub fn require_user(&self) -> Result<(), EdgeError> {
+        match self {
+            Principal::User(_) => Ok(()),
+            Principal::ApiKey(_) => Err(EdgeError::Message("该操作仅限登录用户,api key不可调用".into())),
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Principal
+    where
+        S: Send + Sync,
+        Claims: FromRequestParts<S>,
+{
+    type Rejection = EdgeError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> std::result::Result<Self, Self::Rejection> {
+        if let Some(raw_key) = parts.headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok()) {
+            return api_key_store::verify(raw_key)
+                .map(Principal::ApiKey)
+                .ok_or_else(|| EdgeError::Message("api key无效或已被撤销".into()));
+        }
+
+        let claims = Claims::from_request_parts(parts, state)
+            .await
+            .map_err(|_| EdgeError::Message("未授权,请登录".into()))?;
+        Ok(Principal::User(claims))
+    }
+}
@@ -0,0 +1,36 @@
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use protocol_core::Value;
+
+const CHANNEL_CAPACITY: usize = 1024;
+
+// 点位值变更事件,轮询守护进程和写点位接口都会往这里发布
+#[derive(Debug, Clone, Serialize)]
+pub struct PointUpdate {
+    pub point_id: i32,
+    //产生该point的设备id,调用方知道时才会带上,用于按设备id订阅
+    pub device_id: Option<i32>,
+    pub value: Value,
+    pub ts: u64,
+}
+
+impl PointUpdate {
+    pub fn new(point_id: i32, device_id: Option<i32>, value: Value) -> Self {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+        PointUpdate { point_id, device_id, value, ts }
+    }
+}
+
+static EVENT_BUS: OnceLock<broadcast::Sender<PointUpdate>> = OnceLock::new();
+
+// 全局事件总线:/ws 的每个连接各自订阅一份 Receiver,按客户端的订阅集合过滤转发
+pub fn get_event_bus() -> broadcast::Sender<PointUpdate> {
+    EVENT_BUS.get_or_init(|| broadcast::channel(CHANNEL_CAPACITY).0).clone()
+}
+
+pub fn publish(point_id: i32, device_id: Option<i32>, value: Value) {
+    // 没有订阅者时 send 会返回错误,属于正常情况,直接忽略
+    let _ = get_event_bus().send(PointUpdate::new(point_id, device_id, value));
+}
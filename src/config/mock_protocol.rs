@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::Instant;
+use protocol_core::{Protocol, Value, WriterPointRequest};
+use protocol_core::protocol_store::ProtocolStore;
+use crate::config::error::{EdgeError, Result};
+use crate::models::plugin::{MockBehavior, MockProtocolConfig};
+
+// 单独登记一份 Arc<MockProtocol>,让 mock_handler 能按名字拿到具体类型来做运行时增删改,
+// 而不必给 protocol_core::Protocol trait 加 downcast 能力
+static MOCK_REGISTRY: OnceLock<RwLock<HashMap<String, Arc<MockProtocol>>>> = OnceLock::new();
+
+fn mock_registry() -> &'static RwLock<HashMap<String, Arc<MockProtocol>>> {
+    MOCK_REGISTRY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+pub fn get_mock_protocol(name: &str) -> Result<Arc<MockProtocol>> {
+    mock_registry().read().unwrap().get(name).cloned()
+        .ok_or_else(|| EdgeError::Message(format!("模拟协议不存在:{}", name)))
+}
+
+// 模拟协议:按地址保存一套可运行时调整的行为表,让 read_point/write_point 像对接真实协议一样透明路由过来
+pub struct MockProtocol {
+    name: String,
+    start: Instant,
+    table: RwLock<HashMap<String, MockBehavior>>,
+}
+
+impl MockProtocol {
+    pub fn new(config: MockProtocolConfig) -> Self {
+        let mut table = HashMap::new();
+        for point in config.points {
+            table.insert(point.address, point.behavior);
+        }
+        MockProtocol {
+            name: config.name,
+            start: Instant::now(),
+            table: RwLock::new(table),
+        }
+    }
+
+    // 注册到全局 ProtocolStore,之后按 protocol_name 路由的 read_point/write_point 即可命中它;
+    // 同时登记进 MOCK_REGISTRY,供运行时增删改接口按名字取回
+    pub fn register(config: MockProtocolConfig, store: &ProtocolStore) -> Result<()> {
+        let protocol = Arc::new(MockProtocol::new(config));
+        mock_registry().write().unwrap().insert(protocol.name.clone(), protocol.clone());
+        store.register(protocol.name.clone(), protocol)
+            .map_err(|e| EdgeError::Message(format!("注册模拟协议失败:{}", e)))
+    }
+
+    // 计算当前值,以及写回表里的下一个行为(自增需要推进起点,事件值用完即消失)
+    fn eval(&self, behavior: &MockBehavior) -> (Value, Option<MockBehavior>) {
+        match behavior {
+            MockBehavior::Static(v) => (v.clone(), None),
+            MockBehavior::Event(v, prior) => (v.clone(), Some((**prior).clone())),
+            MockBehavior::AutoIncrement { start, step } => {
+                let value = Value::Float(*start);
+                (value, Some(MockBehavior::AutoIncrement { start: start + step, step: *step }))
+            }
+            MockBehavior::Random { min, max } => {
+                let value = min + (rand_unit() * (max - min));
+                (Value::Float(value), None)
+            }
+            MockBehavior::Sine { amplitude, freq_hz, offset } => {
+                let t = self.start.elapsed().as_secs_f64();
+                let value = amplitude * (2.0 * std::f64::consts::PI * freq_hz * t).sin() + offset;
+                (Value::Float(value), None)
+            }
+        }
+    }
+
+    // 运行时维护接口,供 mock_handler 的增删改路由调用
+    pub fn add_or_set(&self, address: String, behavior: MockBehavior) {
+        self.table.write().unwrap().insert(address, behavior);
+    }
+
+    pub fn remove(&self, address: &str) -> Result<()> {
+        self.table.write().unwrap().remove(address)
+            .map(|_| ())
+            .ok_or_else(|| EdgeError::Message(format!("地址不存在:{}", address)))
+    }
+
+    // 触发一次性事件值:记下当前行为,下一次读取返回事件值后自动恢复,不会把原来的生成器冲掉
+    pub fn emit_event(&self, address: &str, value: Value) {
+        let mut table = self.table.write().unwrap();
+        let prior = table.get(address).cloned().unwrap_or(MockBehavior::Static(value.clone()));
+        table.insert(address.to_string(), MockBehavior::Event(value, Box::new(prior)));
+    }
+}
+
+// ProtocolStore 持有所有权,而运行时增删改接口需要共享引用,故注册时传入 Arc<MockProtocol>
+impl Protocol for Arc<MockProtocol> {
+    fn read_point(&self, request: WriterPointRequest) -> Result<Value> {
+        self.as_ref().read_point(request)
+    }
+
+    fn write_point(&self, request: WriterPointRequest) -> Result<Value> {
+        self.as_ref().write_point(request)
+    }
+}
+
+impl Protocol for MockProtocol {
+    fn read_point(&self, request: WriterPointRequest) -> Result<Value> {
+        let mut table = self.table.write().unwrap();
+        let behavior = table.get(&request.address)
+            .ok_or_else(|| EdgeError::Message(format!("地址不存在:{}", request.address)))?
+            .clone();
+        let (value, next) = self.eval(&behavior);
+        if let Some(next) = next {
+            table.insert(request.address.clone(), next);
+        }
+        Ok(value)
+    }
+
+    fn write_point(&self, request: WriterPointRequest) -> Result<Value> {
+        self.table.write().unwrap()
+            .insert(request.address.clone(), MockBehavior::Static(request.value.clone()));
+        Ok(request.value)
+    }
+}
+
+// 简单的 [0,1) 伪随机数,避免为一个调试用插件引入额外依赖
+fn rand_unit() -> f64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().subsec_nanos();
+    (nanos % 1_000_000) as f64 / 1_000_000.0
+}
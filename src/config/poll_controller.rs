@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+use sqlx::SqlitePool;
+use tokio::task::JoinHandle;
+use protocol_core::{Device, Value};
+use crate::config::device_shadow;
+use crate::config::error::{EdgeError, Result};
+use crate::handler::device_handler::load_all_device_details;
+
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 5;
+
+// 单点位的最新影子值,带采集时间戳
+#[derive(Debug, Clone)]
+pub struct ShadowValue {
+    pub value: Value,
+    pub ts: SystemTime,
+}
+
+// 轮询守护进程:每个设备一个定时任务,定期把点位值刷新进影子缓存
+pub struct PollController {
+    active: AtomicBool,
+    tasks: RwLock<HashMap<String, JoinHandle<()>>>,
+    shadow: RwLock<HashMap<i32, ShadowValue>>,
+}
+
+static POLL_CONTROLLER: OnceLock<PollController> = OnceLock::new();
+
+pub fn get_poll_controller() -> &'static PollController {
+    POLL_CONTROLLER.get_or_init(|| PollController {
+        active: AtomicBool::new(false),
+        tasks: RwLock::new(HashMap::new()),
+        shadow: RwLock::new(HashMap::new()),
+    })
+}
+
+impl PollController {
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    // 启动所有设备的轮询任务;已经在跑的设备会先被停掉再重新起,避免重复任务
+    pub async fn start_all(&'static self, pool: SqlitePool) -> Result<()> {
+        let devices_by_protocol = load_all_device_details(pool.clone()).await?;
+        for devices in devices_by_protocol.values() {
+            for device in devices {
+                self.start_device(device.clone(), pool.clone());
+            }
+        }
+        self.active.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub fn stop_all(&self) {
+        let mut tasks = self.tasks.write().unwrap();
+        for (_, handle) in tasks.drain() {
+            handle.abort();
+        }
+        self.active.store(false, Ordering::Relaxed);
+    }
+
+    // 按设备自定义数据里的 poll_interval_secs 决定轮询周期,缺省用 DEFAULT_POLL_INTERVAL_SECS
+    pub fn start_device(&'static self, device: Device, pool: SqlitePool) {
+        self.stop_device(&device.name);
+
+        let interval_secs = device.custom_data
+            .as_object()
+            .and_then(|m| m.get("poll_interval_secs"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_POLL_INTERVAL_SECS);
+
+        let device_key = device.name.clone();
+        let device_id = device.id;
+        let protocol_name = device.protocol_name.clone();
+        let points = device.points.clone();
+
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(interval_secs));
+            loop {
+                ticker.tick().await;
+                for point in &points {
+                    match device_shadow::read_point(protocol_name.clone(), point.clone().into()) {
+                        Ok(res) => self.update_shadow(pool.clone(), point.id, device_id, res.value).await,
+                        Err(e) => tracing::warn!("设备[{}]轮询point[{}]失败:{}", device_key, point.id, e),
+                    }
+                }
+            }
+        });
+
+        self.tasks.write().unwrap().insert(device.name, handle);
+    }
+
+    pub fn stop_device(&self, device_name: &str) {
+        if let Some(handle) = self.tasks.write().unwrap().remove(device_name) {
+            handle.abort();
+        }
+    }
+
+    async fn update_shadow(&self, pool: SqlitePool, point_id: i32, device_id: i32, value: Value) {
+        crate::config::event_bus::publish(point_id, Some(device_id), value.clone());
+        self.shadow.write().unwrap().insert(point_id, ShadowValue { value: value.clone(), ts: SystemTime::now() });
+        if let Err(e) = crate::config::rule_engine::evaluate(pool, point_id, Some(device_id), &value).await {
+            tracing::warn!("规则引擎求值失败:{}", e);
+        }
+    }
+
+    pub fn get_shadow_value(&self, point_id: i32) -> Option<ShadowValue> {
+        self.shadow.read().unwrap().get(&point_id).cloned()
+    }
+}
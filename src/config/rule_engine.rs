@@ -0,0 +1,131 @@
+use std::sync::{OnceLock, RwLock};
+use std::time::Instant;
+use sqlx::SqlitePool;
+use protocol_core::Value;
+use crate::config::error::Result;
+use crate::models::plugin::{CompareOp, Condition, Rule, RuleAction, RuleEngineConfig, RuleTrigger};
+
+// 规则的运行态:求值结果在规则重复满足期间只触发一次(边沿触发),hold_for_secs 则要求条件
+// 持续为真满一段时间后才算触发
+struct RuleState {
+    //规则所属的插件配置id,load()按这个namespace替换,不会影响其它RuleEngine插件的规则
+    plugin_config_id: i64,
+    rule: Rule,
+    condition_true_since: Option<Instant>,
+    fired: bool,
+}
+
+static RULES: OnceLock<RwLock<Vec<RuleState>>> = OnceLock::new();
+
+fn rules() -> &'static RwLock<Vec<RuleState>> {
+    RULES.get_or_init(|| RwLock::new(Vec::new()))
+}
+
+// 从插件配置(SQLite 里存的 Plugin::RuleEngine)加载规则集,只替换同一个plugin_config_id下的规则,
+// 不同RuleEngine插件各自持有一批规则、互不覆盖
+pub fn load(plugin_config_id: i64, config: RuleEngineConfig) {
+    let mut states = rules().write().unwrap();
+    states.retain(|s| s.plugin_config_id != plugin_config_id);
+    states.extend(config.rules.into_iter()
+        .map(|rule| RuleState { plugin_config_id, rule, condition_true_since: None, fired: false }));
+}
+
+pub fn set_enabled(rule_id: &str, enabled: bool) {
+    if let Some(state) = rules().write().unwrap().iter_mut().find(|s| s.rule.id == rule_id) {
+        state.rule.enabled = enabled;
+        if !enabled {
+            state.condition_true_since = None;
+            state.fired = false;
+        }
+    }
+}
+
+// 在轮询守护进程或writer_point_value产生新值时调用:匹配trigger、求值condition、
+// 边沿触发并应用hold_for_secs时间窗,满足条件的规则执行一次actions
+pub async fn evaluate(pool: SqlitePool, point_id: i32, device_id: Option<i32>, value: &Value) -> Result<()> {
+    let to_fire = {
+        let mut states = rules().write().unwrap();
+        let mut to_fire = Vec::new();
+        for state in states.iter_mut() {
+            if !state.rule.enabled {
+                continue;
+            }
+            let matches = match &state.rule.trigger {
+                RuleTrigger::Point(id) => *id == point_id,
+                RuleTrigger::Device(id) => Some(*id) == device_id,
+            };
+            if !matches {
+                continue;
+            }
+
+            if !eval_condition(&state.rule.condition, value) {
+                state.condition_true_since = None;
+                state.fired = false;
+                continue;
+            }
+
+            let since = *state.condition_true_since.get_or_insert_with(Instant::now);
+            let hold_ok = state.rule.hold_for_secs
+                .map(|secs| since.elapsed().as_secs() >= secs)
+                .unwrap_or(true);
+
+            if hold_ok && !state.fired {
+                state.fired = true;
+                to_fire.push(state.rule.actions.clone());
+            }
+        }
+        to_fire
+    };
+
+    for actions in to_fire {
+        for action in actions {
+            apply_action(pool.clone(), action).await?;
+        }
+    }
+    Ok(())
+}
+
+fn eval_condition(condition: &Condition, value: &Value) -> bool {
+    match condition {
+        Condition::Compare { op, threshold } => {
+            match value_as_f64(value) {
+                Some(v) => compare(*op, v, *threshold),
+                None => false,
+            }
+        }
+        Condition::And(a, b) => eval_condition(a, value) && eval_condition(b, value),
+        Condition::Or(a, b) => eval_condition(a, value) || eval_condition(b, value),
+    }
+}
+
+fn compare(op: CompareOp, value: f64, threshold: f64) -> bool {
+    match op {
+        CompareOp::Gt => value > threshold,
+        CompareOp::Gte => value >= threshold,
+        CompareOp::Lt => value < threshold,
+        CompareOp::Lte => value <= threshold,
+        CompareOp::Eq => (value - threshold).abs() < f64::EPSILON,
+        CompareOp::Neq => (value - threshold).abs() >= f64::EPSILON,
+    }
+}
+
+fn value_as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Float(f) => Some(*f),
+        Value::Int(i) => Some(*i as f64),
+        Value::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+        _ => None,
+    }
+}
+
+async fn apply_action(pool: SqlitePool, action: RuleAction) -> Result<()> {
+    match action {
+        RuleAction::WritePoint { id, value } => {
+            crate::handler::device_handler::write_point_by_id(pool, id, value).await?;
+        }
+        RuleAction::EmitOutput { output_plugin_id, payload } => {
+            tracing::info!("规则引擎向输出插件[{}]发送:{}", output_plugin_id, payload);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use axum::extract::{Path, State};
+use axum::Json;
+use sqlx::SqlitePool;
+use crate::config::api_key_store;
+use crate::config::auth_middleware::Principal;
+use crate::config::error::Result;
+use crate::models::api_key::{ApiKeyRecord, CreateApiKey, CreatedApiKey};
+use crate::models::R;
+
+// 创建一个API key,明文只在这次响应里返回。仅限登录用户,避免api key自己给自己发无限制的新key
+pub async fn create_api_key(principal: Principal, State(pool): State<SqlitePool>, Json(body): Json<CreateApiKey>) -> Result<Json<R<CreatedApiKey>>> {
+    principal.require_user()?;
+    let created = api_key_store::create(&pool, body).await?;
+    Ok(Json(R::success_with_data(created)))
+}
+
+// 仅限登录用户查看key列表
+pub async fn list_api_keys(principal: Principal, State(pool): State<SqlitePool>) -> Result<Json<Vec<ApiKeyRecord>>> {
+    principal.require_user()?;
+    let keys = api_key_store::list(&pool).await?;
+    Ok(Json(keys))
+}
+
+// 撤销立即生效,被撤销的key下一次请求就会被拒绝。仅限登录用户
+pub async fn revoke_api_key(principal: Principal, State(pool): State<SqlitePool>, Path(id): Path<i64>) -> Result<Json<R<String>>> {
+    principal.require_user()?;
+    api_key_store::revoke(&pool, id).await?;
+    Ok(Json(R::success()))
+}
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use axum::extract::{Path, Query, State};
 use axum::Json;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
 use protocol_core::{Device, Point, PointWithProtocolId, Value, WriterPointRequest};
 use crate::config::cache::get_protocol_store;
@@ -9,9 +9,13 @@ use crate::config::error::{EdgeError, Result};
 use crate::models::device::{CreatDevice, DeviceDTO};
 use crate::models::R;
 use crate::config::device_shadow;
+use crate::config::auth_middleware::Principal;
 
 
-pub async fn get_device(State(pool): State<SqlitePool>, Path(id): Path<i32>) -> Result<Json<DeviceDTO>> {
+pub async fn get_device(principal: Principal, State(pool): State<SqlitePool>, Path(id): Path<i32>) -> Result<Json<DeviceDTO>> {
+    if !principal.can_access_device(id) {
+        return Err(EdgeError::Message("api key无权访问该设备".into()));
+    }
     let device = sqlx::query_as::<_, DeviceDTO>("SELECT * FROM tb_device WHERE id = ?")
         .bind(id)
         .fetch_optional(&pool)
@@ -25,7 +29,10 @@ pub async fn get_device(State(pool): State<SqlitePool>, Path(id): Path<i32>) ->
     }
 }
 
-pub async fn get_device_details(State(pool): State<SqlitePool>, Path(id): Path<i32>) -> Result<Json<Device>> {
+pub async fn get_device_details(principal: Principal, State(pool): State<SqlitePool>, Path(id): Path<i32>) -> Result<Json<Device>> {
+    if !principal.can_access_device(id) {
+        return Err(EdgeError::Message("api key无权访问该设备".into()));
+    }
     let device = sqlx::query_as::<_, DeviceDTO>("SELECT * FROM tb_device WHERE id = ?")
         .bind(id)
         .fetch_one(&pool)
@@ -48,8 +55,24 @@ pub async fn get_device_details(State(pool): State<SqlitePool>, Path(id): Path<i
     Ok(Json(device_with_points))
 }
 
-pub async fn read_point_value(State(pool): State<SqlitePool>, Path(id): Path<i32>) -> Result<Json<Value>> {
+pub async fn read_point_value(
+    principal: Principal,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i32>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Value>> {
     let point = get_point_with_protocol_id(pool, id).await?;
+    if !principal.can_access_device(point.device_id) {
+        return Err(EdgeError::Message("api key无权访问该设备".into()));
+    }
+
+    // ?cached=true 时优先读轮询守护进程维护的影子缓存,命中则不再请求协议插件
+    if params.get("cached").map(|v| v == "true").unwrap_or(false) {
+        if let Some(shadow) = crate::config::poll_controller::get_poll_controller().get_shadow_value(id) {
+            return Ok(Json(shadow.value));
+        }
+    }
+
     let res = device_shadow::read_point(point.protocol_name.clone(), point.into())
         .map(|e| e.value)?;
     Ok(Json(res))
@@ -60,9 +83,25 @@ pub struct WriterValue{
     value:Value,
 }
 
-pub async fn writer_point_value(State(pool): State<SqlitePool>,
+pub async fn writer_point_value(principal: Principal,
+                                State(pool): State<SqlitePool>,
                                 Path(id): Path<i32>,
                                 Json(WriterValue{value, .. }): Json<WriterValue>) -> Result<Json<Value>> {
+    if !principal.can_write() {
+        return Err(EdgeError::Message("只读api key无权执行写操作".into()));
+    }
+    let point = get_point_with_protocol_id(pool.clone(), id).await?;
+    if !principal.can_access_device(point.device_id) {
+        return Err(EdgeError::Message("api key无权访问该设备".into()));
+    }
+    let res = write_point_by_id(pool.clone(), id, value).await?;
+    // 带上point所属的device_id,按点位和按设备触发的规则都会求值
+    crate::config::rule_engine::evaluate(pool, id, Some(point.device_id), &res).await?;
+    Ok(Json(res))
+}
+
+// 写点位的核心逻辑,抽出来给规则引擎的 WritePoint 动作复用
+pub(crate) async fn write_point_by_id(pool: SqlitePool, id: i32, value: Value) -> Result<Value> {
     let point = get_point_with_protocol_id(pool, id).await?;
     let store = get_protocol_store().unwrap();
     let protocol_map = store.inner.read().unwrap();
@@ -71,7 +110,203 @@ pub async fn writer_point_value(State(pool): State<SqlitePool>,
     request.value = value;
     let res = protocol.read().unwrap()
         .write_point(request)?;
-    Ok(Json(res))
+    crate::config::event_bus::publish(id, Some(point.device_id), res.clone());
+    Ok(res)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchWriteItem {
+    id: i32,
+    value: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchPointRequest {
+    //要读取的point id列表
+    #[serde(default)]
+    reads: Vec<i32>,
+    //要写入的point id+value列表
+    #[serde(default)]
+    writes: Vec<BatchWriteItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchItemResult {
+    id: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl BatchItemResult {
+    fn ok(id: i32, value: Value) -> Self {
+        BatchItemResult { id, value: Some(value), error: None }
+    }
+
+    fn err(id: i32, error: impl ToString) -> Self {
+        BatchItemResult { id, value: None, error: Some(error.to_string()) }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchPointResponse {
+    reads: Vec<BatchItemResult>,
+    writes: Vec<BatchItemResult>,
+}
+
+// 批量读写端点:一次请求里可同时携带读/写两类操作,单个point失败不影响其余point
+pub async fn batch_point_value(principal: Principal, State(pool): State<SqlitePool>, Json(body): Json<BatchPointRequest>) -> Result<Json<BatchPointResponse>> {
+    let mut ids: Vec<i32> = body.reads.clone();
+    ids.extend(body.writes.iter().map(|item| item.id));
+    ids.sort_unstable();
+    ids.dedup();
+
+    let points = get_points_with_protocol_id(pool.clone(), &ids).await?;
+    let by_id: HashMap<i32, PointWithProtocolId> = points.into_iter().map(|p| (p.point_id, p)).collect();
+
+    // api key限制了设备范围时,越权的point id在结果里直接返回错误,不进入协议层
+    let is_allowed = |id: i32| by_id.get(&id).map(|p| principal.can_access_device(p.device_id)).unwrap_or(true);
+
+    let (allowed_reads, forbidden_reads): (Vec<i32>, Vec<i32>) =
+        body.reads.iter().copied().partition(|id| is_allowed(*id));
+    let (allowed_writes, forbidden_writes): (Vec<BatchWriteItem>, Vec<BatchWriteItem>) =
+        body.writes.into_iter().partition(|item| is_allowed(item.id));
+
+    let mut reads = batch_read(&by_id, &allowed_reads);
+    reads.extend(forbidden_reads.into_iter().map(|id| BatchItemResult::err(id, "api key无权访问该设备")));
+
+    // 批量写入和单点写入一样,只读api key一律拒绝,不单独走设备scope就放行
+    let mut writes = if !allowed_writes.is_empty() && !principal.can_write() {
+        allowed_writes.iter().map(|item| BatchItemResult::err(item.id, "只读api key无权执行写操作")).collect()
+    } else {
+        batch_write(pool, &by_id, &allowed_writes).await
+    };
+    writes.extend(forbidden_writes.into_iter().map(|item| BatchItemResult::err(item.id, "api key无权访问该设备")));
+
+    Ok(Json(BatchPointResponse { reads, writes }))
+}
+
+// 按protocol_name分组,每个协议的读写锁只获取一次,而不是每个point都获取一次。
+// 按下标而不是point id分组,避免请求里出现重复point id时互相挤掉对方的结果
+fn group_indices_by_protocol(by_id: &HashMap<i32, PointWithProtocolId>, ids: &[i32]) -> HashMap<String, Vec<usize>> {
+    let mut grouped: HashMap<String, Vec<usize>> = HashMap::new();
+    for (idx, id) in ids.iter().enumerate() {
+        if let Some(point) = by_id.get(id) {
+            grouped.entry(point.protocol_name.clone()).or_insert_with(Vec::new).push(idx);
+        }
+    }
+    grouped
+}
+
+fn batch_read(by_id: &HashMap<i32, PointWithProtocolId>, ids: &[i32]) -> Vec<BatchItemResult> {
+    let store = get_protocol_store().unwrap();
+    let protocol_map = store.inner.read().unwrap();
+    // 按下标存结果,同一个point id重复出现多次时每次都单独求值、互不覆盖
+    let mut results: Vec<Option<BatchItemResult>> = (0..ids.len()).map(|_| None).collect();
+
+    for (protocol_name, indices) in group_indices_by_protocol(by_id, ids) {
+        let protocol = match protocol_map.get(&protocol_name) {
+            Some(protocol) => protocol,
+            None => {
+                for idx in indices {
+                    results[idx] = Some(BatchItemResult::err(ids[idx], "协议不存在,检查服务配置"));
+                }
+                continue;
+            }
+        };
+        let guard = protocol.read().unwrap();
+        for idx in indices {
+            let id = ids[idx];
+            let point = by_id.get(&id).unwrap().clone();
+            results[idx] = Some(match guard.read_point(point.into()) {
+                Ok(value) => BatchItemResult::ok(id, value),
+                Err(e) => BatchItemResult::err(id, e),
+            });
+        }
+    }
+
+    ids.iter().zip(results.into_iter())
+        .map(|(id, result)| result.unwrap_or_else(|| BatchItemResult::err(*id, "point不存在,请检查请求参数")))
+        .collect()
+}
+
+// 和batch_read一样按protocol_name分组、每个协议的锁只获取一次,同时保持和
+// POST /point/value/:id相同的副作用:每个成功写入的point都发布event_bus事件、触发rule_engine求值。
+// 规则求值涉及await,必须等协议锁(非Send的std::sync守卫)释放之后再做,所以分两段:
+// 先在持锁的同步代码里把所有写操作做完,再在锁释放后逐个await求值
+async fn batch_write(pool: SqlitePool, by_id: &HashMap<i32, PointWithProtocolId>, items: &[BatchWriteItem]) -> Vec<BatchItemResult> {
+    let mut results: Vec<Option<BatchItemResult>> = (0..items.len()).map(|_| None).collect();
+    let mut written: Vec<(i32, i32, Value)> = Vec::new();
+
+    {
+        let store = get_protocol_store().unwrap();
+        let protocol_map = store.inner.read().unwrap();
+
+        let mut grouped: HashMap<String, Vec<usize>> = HashMap::new();
+        for (idx, item) in items.iter().enumerate() {
+            if let Some(point) = by_id.get(&item.id) {
+                grouped.entry(point.protocol_name.clone()).or_insert_with(Vec::new).push(idx);
+            }
+        }
+
+        for (protocol_name, indices) in grouped {
+            let protocol = match protocol_map.get(&protocol_name) {
+                Some(protocol) => protocol,
+                None => {
+                    for idx in indices {
+                        results[idx] = Some(BatchItemResult::err(items[idx].id, "协议不存在,检查服务配置"));
+                    }
+                    continue;
+                }
+            };
+            let guard = protocol.read().unwrap();
+            for idx in indices {
+                let item = &items[idx];
+                let point = by_id.get(&item.id).unwrap().clone();
+                let device_id = point.device_id;
+                let mut request: WriterPointRequest = point.into();
+                request.value = item.value.clone();
+                results[idx] = Some(match guard.write_point(request) {
+                    Ok(value) => {
+                        crate::config::event_bus::publish(item.id, Some(device_id), value.clone());
+                        written.push((item.id, device_id, value.clone()));
+                        BatchItemResult::ok(item.id, value)
+                    }
+                    Err(e) => BatchItemResult::err(item.id, e),
+                });
+            }
+        }
+    }
+
+    for (id, device_id, value) in written {
+        if let Err(e) = crate::config::rule_engine::evaluate(pool.clone(), id, Some(device_id), &value).await {
+            tracing::warn!("批量写入后规则引擎求值失败[point_id={}]:{}", id, e);
+        }
+    }
+
+    items.iter().enumerate()
+        .map(|(idx, item)| results[idx].take().unwrap_or_else(|| BatchItemResult::err(item.id, "point不存在,请检查请求参数")))
+        .collect()
+}
+
+async fn get_points_with_protocol_id(pool: SqlitePool, ids: &[i32]) -> Result<Vec<PointWithProtocolId>> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+    let sql = format!(r#"
+    SELECT tb_point.id AS point_id, tb_point.device_id, tb_point.address, tb_point.data_type, tb_point.access_mode,
+       tb_point.multiplier, tb_point.precision, tb_point.description, tb_point.part_number, tb_device.protocol_name AS protocol_name
+        FROM tb_point
+        JOIN tb_device ON tb_point.device_id = tb_device.id
+        WHERE tb_point.id IN ({});
+    "#, placeholders);
+    let mut query = sqlx::query_as::<_, PointWithProtocolId>(&sql);
+    for id in ids {
+        query = query.bind(id);
+    }
+    Ok(query.fetch_all(&pool).await?)
 }
 
 async fn get_point_with_protocol_id(pool: SqlitePool, id: i32) -> Result<PointWithProtocolId> {
@@ -122,7 +357,10 @@ pub async fn load_all_device_details(pool: SqlitePool) -> Result<HashMap<String,
     Ok(res)
 }
 
-pub async fn create_device(State(pool): State<SqlitePool>, device: Json<CreatDevice>) -> Result<Json<R<DeviceDTO>>> {
+pub async fn create_device(principal: Principal, State(pool): State<SqlitePool>, device: Json<CreatDevice>) -> Result<Json<R<DeviceDTO>>> {
+    if !principal.can_write() {
+        return Err(EdgeError::Message("只读api key无权执行写操作".into()));
+    }
     let created_device = sqlx::query_as::<_, DeviceDTO>(
         "INSERT INTO tb_device (name, device_type, custom_data, protocol_name) VALUES (?, ?, ?, ?) RETURNING *",
     )
@@ -137,10 +375,17 @@ pub async fn create_device(State(pool): State<SqlitePool>, device: Json<CreatDev
 }
 
 pub async fn update_device(
+    principal: Principal,
     State(pool): State<SqlitePool>,
     Path(id): Path<i32>,
     Json(device): Json<DeviceDTO>,
 ) -> Result<Json<R<String>>> {
+    if !principal.can_write() {
+        return Err(EdgeError::Message("只读api key无权执行写操作".into()));
+    }
+    if !principal.can_access_device(id) {
+        return Err(EdgeError::Message("api key无权访问该设备".into()));
+    }
     let updated_device = sqlx::query(
         "UPDATE tb_device SET name = $1, device_type = $2, custom_data = $3, protocol_name = $4 WHERE id = $5",
     )
@@ -162,7 +407,13 @@ pub async fn update_device(
     }
 }
 
-pub async fn delete_device(State(pool): State<SqlitePool>, Path(device_id): Path<i32>) -> Result<Json<R<String>>> {
+pub async fn delete_device(principal: Principal, State(pool): State<SqlitePool>, Path(device_id): Path<i32>) -> Result<Json<R<String>>> {
+    if !principal.can_write() {
+        return Err(EdgeError::Message("只读api key无权执行写操作".into()));
+    }
+    if !principal.can_access_device(device_id) {
+        return Err(EdgeError::Message("api key无权访问该设备".into()));
+    }
     sqlx::query("DELETE FROM tb_device WHERE id = ?")
         .bind(device_id)
         .execute(&pool)
@@ -0,0 +1,65 @@
+use axum::extract::{Json, Path};
+use serde::Deserialize;
+use protocol_core::Value;
+use crate::config::auth_middleware::Principal;
+use crate::config::error::{EdgeError, Result};
+use crate::config::mock_protocol::get_mock_protocol;
+use crate::models::plugin::MockBehavior;
+use crate::models::R;
+
+#[derive(Debug, Deserialize)]
+pub struct SetMockBehavior {
+    address: String,
+    behavior: MockBehavior,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetMockValue {
+    address: String,
+    value: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RemoveMockAddress {
+    address: String,
+}
+
+// 新增或覆盖一个地址的模拟行为(静态值/自增/随机/正弦)
+pub async fn add_mock_behavior(principal: Principal, Path(protocol_name): Path<String>, Json(body): Json<SetMockBehavior>) -> Result<Json<R<String>>> {
+    if !principal.can_write() {
+        return Err(EdgeError::Message("只读api key无权执行写操作".into()));
+    }
+    let protocol = get_mock_protocol(&protocol_name)?;
+    protocol.add_or_set(body.address, body.behavior);
+    Ok(Json(R::success()))
+}
+
+// 删除一个地址的模拟行为
+pub async fn remove_mock_behavior(principal: Principal, Path(protocol_name): Path<String>, Json(body): Json<RemoveMockAddress>) -> Result<Json<R<String>>> {
+    if !principal.can_write() {
+        return Err(EdgeError::Message("只读api key无权执行写操作".into()));
+    }
+    let protocol = get_mock_protocol(&protocol_name)?;
+    protocol.remove(&body.address)?;
+    Ok(Json(R::success()))
+}
+
+// 直接把某个地址改写为固定值,等价于 add_or_set 一个 Static 行为
+pub async fn set_mock_value(principal: Principal, Path(protocol_name): Path<String>, Json(body): Json<SetMockValue>) -> Result<Json<R<String>>> {
+    if !principal.can_write() {
+        return Err(EdgeError::Message("只读api key无权执行写操作".into()));
+    }
+    let protocol = get_mock_protocol(&protocol_name)?;
+    protocol.add_or_set(body.address, MockBehavior::Static(body.value));
+    Ok(Json(R::success()))
+}
+
+// 触发一个一次性事件值,下一次 read_point 返回它,之后自动恢复为触发前的行为
+pub async fn emit_mock_event(principal: Principal, Path(protocol_name): Path<String>, Json(body): Json<SetMockValue>) -> Result<Json<R<String>>> {
+    if !principal.can_write() {
+        return Err(EdgeError::Message("只读api key无权执行写操作".into()));
+    }
+    let protocol = get_mock_protocol(&protocol_name)?;
+    protocol.emit_event(&body.address, body.value);
+    Ok(Json(R::success()))
+}
@@ -0,0 +1,98 @@
+use axum::extract::State;
+use axum::Json;
+use sqlx::{FromRow, SqlitePool};
+use validator::Validate;
+use crate::config::auth_middleware::Principal;
+use crate::config::cache::get_protocol_store;
+use crate::config::error::{EdgeError, Result};
+use crate::config::mock_protocol::MockProtocol;
+use crate::config::rule_engine;
+use crate::models::plugin::{CreatePlugin, CreatePluginConfig, Plugin, PluginConfig, PluginType, ProtocolConfig};
+use crate::models::R;
+
+#[derive(Debug, FromRow)]
+struct PluginConfigRow {
+    id: i64,
+    description: Option<String>,
+    form_customization: Option<String>,
+    plugin_type: PluginType,
+    plugin_data: String,
+}
+
+// 创建插件配置:落库后立即按插件类型完成运行时接入(注册模拟协议、加载规则引擎);真实协议只校验库路径,这里不加载.dll/.so
+pub async fn create_plugin_config(principal: Principal, State(pool): State<SqlitePool>, Json(body): Json<CreatePluginConfig>) -> Result<Json<R<PluginConfig>>> {
+    if !principal.can_write() {
+        return Err(EdgeError::Message("只读api key无权执行写操作".into()));
+    }
+    if let CreatePlugin::Protocol(ref protocol) = body.plugin {
+        protocol.validate().map_err(|e| EdgeError::Message(e.to_string()))?;
+    }
+
+    let plugin_data = serde_json::to_string(&body.plugin).map_err(|e| EdgeError::Message(e.to_string()))?;
+
+    let row = sqlx::query_as::<_, PluginConfigRow>(
+        "INSERT INTO tb_plugin_config (description, form_customization, plugin_type, plugin_data) VALUES (?, ?, ?, ?) RETURNING *",
+    )
+        .bind(&body.description)
+        .bind(&body.form_customization)
+        .bind(body.plugin_type)
+        .bind(&plugin_data)
+        .fetch_one(&pool)
+        .await?;
+
+    let plugin = activate_plugin(row.id, body.plugin)?;
+
+    Ok(Json(R::success_with_data(PluginConfig {
+        id: row.id,
+        description: row.description,
+        form_customization: row.form_customization,
+        plugin,
+        plugin_type: row.plugin_type,
+    })))
+}
+
+// 服务启动时把已持久化的插件配置重新接入运行时(重新注册模拟协议、重新加载规则引擎)
+pub async fn load_persisted_plugins(pool: &SqlitePool) -> Result<()> {
+    let rows = sqlx::query_as::<_, PluginConfigRow>("SELECT * FROM tb_plugin_config")
+        .fetch_all(pool)
+        .await?;
+
+    for row in rows {
+        let create: CreatePlugin = match serde_json::from_str(&row.plugin_data) {
+            Ok(create) => create,
+            Err(e) => {
+                tracing::warn!("插件配置[{}]反序列化失败,跳过:{}", row.id, e);
+                continue;
+            }
+        };
+        if let Err(e) = activate_plugin(row.id, create) {
+            tracing::warn!("插件配置[{}]启动重新接入失败:{}", row.id, e);
+        }
+    }
+    Ok(())
+}
+
+// 按插件类型完成运行时接入,真实协议的.dll/.so加载由协议加载层负责,这里只处理本仓新增的两类插件
+fn activate_plugin(plugin_config_id: i64, create: CreatePlugin) -> Result<Plugin> {
+    match create {
+        CreatePlugin::Protocol(config) => {
+            Ok(Plugin::Protocol(ProtocolConfig {
+                id: plugin_config_id,
+                name: config.name,
+                path: config.path,
+                description: config.description,
+                plugin_config_id,
+            }))
+        }
+        CreatePlugin::MockProtocol(config) => {
+            let store = get_protocol_store().unwrap();
+            MockProtocol::register(config.clone(), &store)?;
+            Ok(Plugin::MockProtocol(config))
+        }
+        CreatePlugin::DataOutput(config) => Ok(Plugin::DataOutput(config)),
+        CreatePlugin::RuleEngine(config) => {
+            rule_engine::load(plugin_config_id, config.clone());
+            Ok(Plugin::RuleEngine(config))
+        }
+    }
+}
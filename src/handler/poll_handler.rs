@@ -0,0 +1,63 @@
+use axum::extract::{Path, State};
+use axum::Json;
+use sqlx::SqlitePool;
+use crate::config::auth_middleware::Principal;
+use crate::config::error::{EdgeError, Result};
+use crate::config::poll_controller::get_poll_controller;
+use crate::handler::device_handler::load_all_device_details;
+use crate::models::R;
+
+// 全局启动轮询:为每个设备各自起一个定时任务
+pub async fn start_polling(principal: Principal, State(pool): State<SqlitePool>) -> Result<Json<R<String>>> {
+    if !principal.can_write() {
+        return Err(EdgeError::Message("只读api key无权执行写操作".into()));
+    }
+    get_poll_controller().start_all(pool).await?;
+    Ok(Json(R::success()))
+}
+
+// 全局停止轮询,所有设备任务一并取消
+pub async fn stop_polling(principal: Principal) -> Result<Json<R<String>>> {
+    if !principal.can_write() {
+        return Err(EdgeError::Message("只读api key无权执行写操作".into()));
+    }
+    get_poll_controller().stop_all();
+    Ok(Json(R::success()))
+}
+
+// 单独重启某个设备的轮询任务,用于调整采集频率后立即生效
+pub async fn start_device_polling(principal: Principal, State(pool): State<SqlitePool>, Path(device_id): Path<i32>) -> Result<Json<R<String>>> {
+    if !principal.can_write() {
+        return Err(EdgeError::Message("只读api key无权执行写操作".into()));
+    }
+    let devices_by_protocol = load_all_device_details(pool.clone()).await?;
+    let device = devices_by_protocol.values()
+        .flatten()
+        .find(|d| d.id == device_id)
+        .cloned()
+        .ok_or_else(|| EdgeError::Message("设备不存在".into()))?;
+    if !principal.can_access_device(device.id) {
+        return Err(EdgeError::Message("api key无权访问该设备".into()));
+    }
+    get_poll_controller().start_device(device, pool);
+    Ok(Json(R::success()))
+}
+
+// 和start_device_polling用同一个device_id,不强迫调用方另外记一套设备名;
+// PollController内部仍然按设备名维护任务表,这里查一次名字转换过去
+pub async fn stop_device_polling(principal: Principal, State(pool): State<SqlitePool>, Path(device_id): Path<i32>) -> Result<Json<R<String>>> {
+    if !principal.can_write() {
+        return Err(EdgeError::Message("只读api key无权执行写操作".into()));
+    }
+    let devices_by_protocol = load_all_device_details(pool.clone()).await?;
+    let device = devices_by_protocol.values()
+        .flatten()
+        .find(|d| d.id == device_id)
+        .cloned()
+        .ok_or_else(|| EdgeError::Message("设备不存在".into()))?;
+    if !principal.can_access_device(device.id) {
+        return Err(EdgeError::Message("api key无权访问该设备".into()));
+    }
+    get_poll_controller().stop_device(&device.name);
+    Ok(Json(R::success()))
+}
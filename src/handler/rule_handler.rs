@@ -0,0 +1,20 @@
+use axum::extract::{Json, Path};
+use serde::Deserialize;
+use crate::config::auth_middleware::Principal;
+use crate::config::error::{EdgeError, Result};
+use crate::config::rule_engine;
+use crate::models::R;
+
+#[derive(Debug, Deserialize)]
+pub struct SetRuleEnabled {
+    enabled: bool,
+}
+
+// 启用/停用单条规则,不需要重新提交整份RuleEngineConfig
+pub async fn set_rule_enabled(principal: Principal, Path(rule_id): Path<String>, Json(body): Json<SetRuleEnabled>) -> Result<Json<R<String>>> {
+    if !principal.can_write() {
+        return Err(EdgeError::Message("只读api key无权执行写操作".into()));
+    }
+    rule_engine::set_enabled(&rule_id, body.enabled);
+    Ok(Json(R::success()))
+}
@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::Extension;
+use axum::response::Response;
+use serde::Deserialize;
+use tokio::sync::broadcast::Sender;
+use crate::config::auth_middleware::Principal;
+use crate::config::event_bus::PointUpdate;
+
+// 客户端通过文本帧下发订阅集合,之后只收到命中订阅的推送。point_ids和device_ids可以同时下发,
+// 命中任意一个集合就转发
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum Subscription {
+    Subscribe {
+        #[serde(default)]
+        point_ids: Vec<i32>,
+        #[serde(default)]
+        device_ids: Vec<i32>,
+    },
+    Unsubscribe {
+        #[serde(default)]
+        point_ids: Vec<i32>,
+        #[serde(default)]
+        device_ids: Vec<i32>,
+    },
+}
+
+// 一个连接当前生效的订阅集合,两个集合都为空时表示不过滤,转发所有更新
+#[derive(Default)]
+struct SubscribedSet {
+    point_ids: HashSet<i32>,
+    device_ids: HashSet<i32>,
+}
+
+impl SubscribedSet {
+    fn is_empty(&self) -> bool {
+        self.point_ids.is_empty() && self.device_ids.is_empty()
+    }
+
+    fn matches(&self, update: &PointUpdate) -> bool {
+        self.is_empty()
+            || self.point_ids.contains(&update.point_id)
+            || update.device_id.map(|id| self.device_ids.contains(&id)).unwrap_or(false)
+    }
+}
+
+pub async fn ws_upgrade(principal: Principal, ws: WebSocketUpgrade, Extension(bus): Extension<Sender<PointUpdate>>) -> Response {
+    ws.on_upgrade(move |socket| handle_socket(socket, bus, principal))
+}
+
+async fn handle_socket(mut socket: WebSocket, bus: Sender<PointUpdate>, principal: Principal) {
+    let mut subscribed = SubscribedSet::default();
+    let mut events = bus.subscribe();
+
+    loop {
+        tokio::select! {
+            msg = socket.recv() => {
+                match msg {
+                    Some(Ok(Message::Text(text))) => {
+                        if let Ok(sub) = serde_json::from_str::<Subscription>(&text) {
+                            apply_subscription(&mut subscribed, sub, &principal);
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Err(_)) => break,
+                    _ => {}
+                }
+            }
+            update = events.recv() => {
+                match update {
+                    Ok(update) if is_visible_to(&principal, &update) && subscribed.matches(&update) => {
+                        let payload = serde_json::to_string(&update).unwrap_or_default();
+                        if socket.send(Message::Text(payload)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(_) => break,
+                }
+            }
+        }
+    }
+}
+
+// 设备范围受限的api key:没有device_id的更新无法核实归属,保守地不转发;
+// 有device_id时必须落在key的授权设备列表里
+fn is_visible_to(principal: &Principal, update: &PointUpdate) -> bool {
+    if !principal.is_device_scoped() {
+        return true;
+    }
+    match update.device_id {
+        Some(device_id) => principal.can_access_device(device_id),
+        None => false,
+    }
+}
+
+// 丢弃越权的device_id订阅项,不让设备范围受限的api key绕过自己的scope
+fn apply_subscription(subscribed: &mut SubscribedSet, sub: Subscription, principal: &Principal) {
+    match sub {
+        Subscription::Subscribe { point_ids, device_ids } => {
+            subscribed.point_ids.extend(point_ids);
+            subscribed.device_ids.extend(device_ids.into_iter().filter(|id| principal.can_access_device(*id)));
+        }
+        Subscription::Unsubscribe { point_ids, device_ids } => {
+            for id in point_ids {
+                subscribed.point_ids.remove(&id);
+            }
+            for id in device_ids {
+                subscribed.device_ids.remove(&id);
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+
+// API key的访问范围:只读 or 可读写
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Copy, PartialEq, Eq)]
+pub enum KeyScope {
+    #[serde(rename = "ReadOnly")]
+    ReadOnly,
+    #[serde(rename = "ReadWrite")]
+    ReadWrite,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKey {
+    pub name: String,
+    pub scope: KeyScope,
+    //限定只能访问的设备id,留空表示不限制
+    pub device_ids: Option<Vec<i32>>,
+}
+
+// 持久化记录,hashed_key只存哈希,明文只在创建时返回一次
+#[derive(Debug, Serialize, FromRow)]
+pub struct ApiKeyRecord {
+    pub id: i64,
+    pub name: String,
+    #[serde(skip_serializing)]
+    pub hashed_key: String,
+    pub scope: KeyScope,
+    pub device_ids: Option<sqlx::types::Json<Vec<i32>>>,
+    pub revoked: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreatedApiKey {
+    pub id: i64,
+    pub name: String,
+    //明文key仅在创建响应里出现一次,之后无法再次获取
+    pub key: String,
+}
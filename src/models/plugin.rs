@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, Type};
 use validator::{Validate, ValidationError, ValidationErrors};
 use std::env::consts::DLL_EXTENSION;
+use protocol_core::Value;
 use crate::config::error::EdgeError;
 
 // 公共的插件配置,创建使用
@@ -40,14 +41,16 @@ pub enum PluginType {
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Plugin {
     Protocol(ProtocolConfig),
+    MockProtocol(MockProtocolConfig),
     DataOutput(DataOutputConfig),
     RuleEngine(RuleEngineConfig),
 }
 
 // 插件类型枚举
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub enum CreatePlugin {
     Protocol(CreateProtocolConfig),
+    MockProtocol(MockProtocolConfig),
     DataOutput(DataOutputConfig),
     RuleEngine(RuleEngineConfig),
 }
@@ -67,7 +70,7 @@ pub struct ProtocolConfig {
 }
 
 // 南向协议解析插件配置
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct CreateProtocolConfig {
     //协议名称
     pub name: String,
@@ -94,6 +97,40 @@ fn validate_path(path: &str) -> Result<(), ValidationError> {
     }
 }
 
+// 模拟协议插件配置,用于在没有真实硬件/协议库时联调
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MockProtocolConfig {
+    //协议名称,注册到 ProtocolStore 的 key
+    pub name: String,
+    //协议描述
+    pub description: Option<String>,
+    //地址 -> 模拟行为 的初始表
+    pub points: Vec<MockPointConfig>,
+}
+
+// 单个地址的模拟行为配置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MockPointConfig {
+    //点位地址
+    pub address: String,
+    pub behavior: MockBehavior,
+}
+
+// 模拟值的生成方式
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum MockBehavior {
+    //固定值,读取时原样返回
+    Static(Value),
+    //一次性事件值,下一次读取返回后即恢复为触发前的行为(保存在Box里,恢复后不会丢失AutoIncrement/Sine等生成器的状态)
+    Event(Value, Box<MockBehavior>),
+    //每次读取自增 step,从 start 开始
+    AutoIncrement { start: f64, step: f64 },
+    //区间内的随机值
+    Random { min: f64, max: f64 },
+    //正弦波形:value = amplitude * sin(2*pi*freq_hz*t) + offset
+    Sine { amplitude: f64, freq_hz: f64, offset: f64 },
+}
+
 // 北向数据输出插件配置
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DataOutputConfig {
@@ -101,9 +138,58 @@ pub struct DataOutputConfig {
     // ...
 }
 
-// 规则引擎插件配置
-#[derive(Debug, Serialize, Deserialize)]
+// 规则引擎插件配置:一组按顺序求值的规则
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RuleEngineConfig {
-    // 规则引擎插件特有的字段
-    // ...
+    pub rules: Vec<Rule>,
+}
+
+// 单条规则:trigger命中后求值condition,边沿触发一次actions
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Rule {
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub trigger: RuleTrigger,
+    pub condition: Condition,
+    //条件需要持续为真多久才触发,缺省为立即触发
+    pub hold_for_secs: Option<u64>,
+    pub actions: Vec<RuleAction>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+// 规则的触发源:某个点位或某个设备产生新值时求值
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum RuleTrigger {
+    Point(i32),
+    Device(i32),
+}
+
+// 条件表达式,支持比较运算和AND/OR组合
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum Condition {
+    Compare { op: CompareOp, threshold: f64 },
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub enum CompareOp {
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Eq,
+    Neq,
+}
+
+// 条件满足后执行的动作
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum RuleAction {
+    WritePoint { id: i32, value: Value },
+    EmitOutput { output_plugin_id: i64, payload: String },
 }
\ No newline at end of file
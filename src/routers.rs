@@ -5,19 +5,34 @@ use crate::handler::things::{get_product_by_id, get_product_funcs};
 use sqlx::{SqlitePool};
 use protocol_core::protocol_store::ProtocolStore;
 use crate::handler::plugin_handler::create_plugin_config;
-use crate::handler::device_handler::{create_device, delete_device, get_device, read_point_value, update_device, writer_point_value};
+use crate::handler::device_handler::{batch_point_value, create_device, delete_device, get_device, read_point_value, update_device, writer_point_value};
 use crate::config::cache::{get_protocol_store, set_protocol_store};
 use crate::config::error::EdgeError;
-use crate::handler::auth_handler;
 use crate::handler::auth_handler::login;
 use crate::handler::point_handler::{create_point, delete_point, get_point, update_point};
+use crate::handler::mock_handler::{add_mock_behavior, emit_mock_event, remove_mock_behavior, set_mock_value};
+use crate::handler::poll_handler::{start_device_polling, start_polling, stop_device_polling, stop_polling};
+use crate::handler::rule_handler::set_rule_enabled;
+use crate::handler::ws_handler::ws_upgrade;
+use crate::config::event_bus::get_event_bus;
+use crate::config::auth_middleware::Principal;
+use crate::handler::api_key_handler::{create_api_key, list_api_keys, revoke_api_key};
 
 pub fn register(pool: SqlitePool) -> Result<Router,EdgeError> {
     set_protocol_store(ProtocolStore::new())?;
     Ok(Router::new()
         .nest("/", routers())
         .with_state(pool)
-        .layer(Extension(get_protocol_store().unwrap())))
+        .layer(Extension(get_protocol_store().unwrap()))
+        .layer(Extension(get_event_bus())))
+}
+
+// 服务启动时的异步初始化,必须在register()之后、开始接受请求前await完成:
+// 加载未撤销的api key,并把已持久化的插件配置重新接入运行时
+pub async fn init(pool: &SqlitePool) -> crate::config::error::Result<()> {
+    crate::config::api_key_store::init(pool).await?;
+    crate::handler::plugin_handler::load_persisted_plugins(pool).await?;
+    Ok(())
 }
 
 
@@ -43,10 +58,29 @@ pub fn need_auth_routers() -> Router<SqlitePool> {
         .route("/point/:id", delete(delete_point))
         .route("/point/value/:id", get(read_point_value))
         .route("/point/value/:id", post(writer_point_value))
+        .route("/point/value/batch", post(batch_point_value))
+        //轮询守护进程
+        .route("/poll/start", post(start_polling))
+        .route("/poll/stop", post(stop_polling))
+        .route("/poll/device/:device_id/start", post(start_device_polling))
+        .route("/poll/device/:device_id/stop", post(stop_device_polling))
         //创建插件
         .route("/plugin", post(create_plugin_config),
         )
-        .layer(from_extractor::<auth_handler::Claims>())
+        //模拟协议:运行时增删改地址->值映射,触发一次性事件
+        .route("/plugin/mock/:protocol_name/behavior", post(add_mock_behavior))
+        .route("/plugin/mock/:protocol_name/behavior", delete(remove_mock_behavior))
+        .route("/plugin/mock/:protocol_name/value", post(set_mock_value))
+        .route("/plugin/mock/:protocol_name/event", post(emit_mock_event))
+        //点位/影子值实时推送
+        .route("/ws", get(ws_upgrade))
+        //规则引擎:启用/停用单条规则
+        .route("/rule/:rule_id/enabled", post(set_rule_enabled))
+        //api key管理:创建/查看/撤销,撤销立即生效
+        .route("/api-key", post(create_api_key))
+        .route("/api-key", get(list_api_keys))
+        .route("/api-key/:id", delete(revoke_api_key))
+        .layer(from_extractor::<Principal>())
 }
 
 //不需要权限认证的路由